@@ -0,0 +1,255 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::fs;
+use std::io::Error;
+use std::path::{Path, PathBuf};
+
+/// Backup control for pre-existing destination files, modeled on GNU
+/// `install`'s `--backup[=CONTROL]` option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackupMode {
+    /// Overwrite the destination without keeping a copy.
+    #[default]
+    None,
+    /// Always back up as `NAME<suffix>`, overwriting any previous backup.
+    Simple,
+    /// Always back up as `NAME.~N~`, picking the next unused `N`.
+    Numbered,
+    /// Use numbered backups if one already exists for `NAME`, simple otherwise.
+    Existing,
+}
+
+impl BackupMode {
+    pub const ALL: [BackupMode; 4] = [BackupMode::None, BackupMode::Simple, BackupMode::Numbered, BackupMode::Existing];
+}
+
+impl std::fmt::Display for BackupMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            BackupMode::None => "No backup",
+            BackupMode::Simple => "Simple (~)",
+            BackupMode::Numbered => "Numbered (.~N~)",
+            BackupMode::Existing => "Existing",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+pub const DEFAULT_SUFFIX: &str = "~";
+
+/// Strips path separators from a user-supplied backup suffix so it can never redirect the
+/// backup into a different directory than the destination it's backing up (e.g. a suffix of
+/// `/../../tmp/x`).
+pub fn sanitize_suffix(suffix: &str) -> String {
+    suffix.chars().filter(|c| !std::path::is_separator(*c)).collect()
+}
+
+/// If `dst` exists, renames it out of the way according to `mode` and
+/// returns the path it was moved to. Returns `None` if there was nothing
+/// to back up, i.e. `mode` is `None` or `dst` doesn't exist yet.
+pub fn backup_existing(dst: &Path, mode: BackupMode, suffix: &str) -> Result<Option<PathBuf>, Error> {
+    if mode == BackupMode::None || !dst.try_exists()? {
+        return Ok(None)
+    }
+
+    let backup = match mode {
+        BackupMode::None => unreachable!(),
+        BackupMode::Simple => simple_backup_path(dst, suffix),
+        BackupMode::Numbered => next_numbered_backup_path(dst)?,
+        BackupMode::Existing => {
+            match max_numbered_backup(dst)? {
+                Some(n) => numbered_backup_path(dst, n + 1),
+                None => simple_backup_path(dst, suffix),
+            }
+        }
+    };
+
+    fs::rename(dst, &backup)?;
+    Ok(Some(backup))
+}
+
+fn simple_backup_path(dst: &Path, suffix: &str) -> PathBuf {
+    let sanitized = sanitize_suffix(suffix);
+    // an empty suffix would make the backup path equal to `dst` itself, turning the rename
+    // in `backup_existing` into a same-path no-op that silently destroys the original file
+    let sanitized = if sanitized.is_empty() { DEFAULT_SUFFIX.to_string() } else { sanitized };
+
+    let mut name = dst.as_os_str().to_os_string();
+    name.push(sanitized);
+    PathBuf::from(name)
+}
+
+fn numbered_backup_path(dst: &Path, n: u32) -> PathBuf {
+    let mut name = dst.as_os_str().to_os_string();
+    name.push(format!(".~{}~", n));
+    PathBuf::from(name)
+}
+
+fn next_numbered_backup_path(dst: &Path) -> Result<PathBuf, Error> {
+    let n = max_numbered_backup(dst)?.unwrap_or(0) + 1;
+    Ok(numbered_backup_path(dst, n))
+}
+
+// highest existing `NAME.~N~` backup's N, scanning all siblings rather than stopping at the
+// first free slot, so a gap left by manually deleting an earlier numbered backup (e.g. .~2~
+// while .~1~ and .~3~ remain) can't make the next backup refill that gap with fresher content
+// than .~3~ and break the "higher N = more recent" invariant GNU `install` models
+fn max_numbered_backup(dst: &Path) -> Result<Option<u32>, Error> {
+    let (Some(parent), Some(file_name)) = (dst.parent(), dst.file_name().and_then(|n| n.to_str())) else {
+        return Ok(None)
+    };
+
+    if !parent.try_exists()? {
+        return Ok(None)
+    }
+
+    let prefix = format!("{}.~", file_name);
+    let mut max = None;
+    for entry in fs::read_dir(parent)? {
+        let name = entry?.file_name();
+        let Some(name) = name.to_str() else { continue };
+        let Some(n) = name.strip_prefix(&prefix).and_then(|n| n.strip_suffix('~')) else { continue };
+        let Ok(n) = n.parse::<u32>() else { continue };
+        max = Some(max.map_or(n, |m: u32| m.max(n)));
+    }
+
+    Ok(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scratch_dir;
+
+    #[test]
+    fn none_leaves_destination_in_place() {
+        let dir = scratch_dir("backup");
+        let dst = dir.join("config-cache.wtf");
+        fs::write(&dst, "old").unwrap();
+
+        let result = backup_existing(&dst, BackupMode::None, DEFAULT_SUFFIX).unwrap();
+
+        assert!(result.is_none());
+        assert!(dst.try_exists().unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn missing_destination_is_not_backed_up() {
+        let dir = scratch_dir("backup");
+        let dst = dir.join("config-cache.wtf");
+
+        let result = backup_existing(&dst, BackupMode::Simple, DEFAULT_SUFFIX).unwrap();
+
+        assert!(result.is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn simple_renames_with_suffix_and_overwrites_previous_backup() {
+        let dir = scratch_dir("backup");
+        let dst = dir.join("config-cache.wtf");
+        fs::write(&dst, "first").unwrap();
+
+        let backup = backup_existing(&dst, BackupMode::Simple, "~").unwrap().unwrap();
+        assert_eq!(backup, dir.join("config-cache.wtf~"));
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "first");
+
+        fs::write(&dst, "second").unwrap();
+        let backup = backup_existing(&dst, BackupMode::Simple, "~").unwrap().unwrap();
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "second");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn simple_strips_path_separators_from_a_malicious_suffix() {
+        let dir = scratch_dir("backup");
+        let dst = dir.join("config-cache.wtf");
+        fs::write(&dst, "first").unwrap();
+
+        let backup = backup_existing(&dst, BackupMode::Simple, "/../../tmp/x").unwrap().unwrap();
+
+        // the separators are stripped, so the backup stays next to `dst` instead of escaping it
+        assert_eq!(backup, dir.join("config-cache.wtf....tmpx"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn simple_falls_back_to_default_suffix_when_given_an_empty_one() {
+        let dir = scratch_dir("backup");
+        let dst = dir.join("config-cache.wtf");
+        fs::write(&dst, "original").unwrap();
+
+        let backup = backup_existing(&dst, BackupMode::Simple, "").unwrap().unwrap();
+
+        // an empty suffix must never collapse the backup path onto `dst` itself: that would
+        // turn the rename into a same-path no-op that silently destroys the original file
+        assert_ne!(backup, dst);
+        assert_eq!(backup, dir.join(format!("config-cache.wtf{}", DEFAULT_SUFFIX)));
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "original");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn numbered_picks_next_unused_index() {
+        let dir = scratch_dir("backup");
+        let dst = dir.join("config-cache.wtf");
+        fs::write(&dst, "v1").unwrap();
+
+        let first = backup_existing(&dst, BackupMode::Numbered, DEFAULT_SUFFIX).unwrap().unwrap();
+        assert_eq!(first, dir.join("config-cache.wtf.~1~"));
+
+        fs::write(&dst, "v2").unwrap();
+        let second = backup_existing(&dst, BackupMode::Numbered, DEFAULT_SUFFIX).unwrap().unwrap();
+        assert_eq!(second, dir.join("config-cache.wtf.~2~"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn existing_falls_back_to_simple_until_a_numbered_backup_exists() {
+        let dir = scratch_dir("backup");
+        let dst = dir.join("config-cache.wtf");
+        fs::write(&dst, "v1").unwrap();
+
+        let first = backup_existing(&dst, BackupMode::Existing, "~").unwrap().unwrap();
+        assert_eq!(first, dir.join("config-cache.wtf~"));
+
+        fs::write(&dst, "v2").unwrap();
+        fs::write(dir.join("config-cache.wtf.~1~"), "manually numbered").unwrap();
+        let second = backup_existing(&dst, BackupMode::Existing, "~").unwrap().unwrap();
+        assert_eq!(second, dir.join("config-cache.wtf.~2~"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn existing_detects_a_numbered_backup_even_if_dot_tilde_1_tilde_is_missing() {
+        let dir = scratch_dir("backup");
+        let dst = dir.join("config-cache.wtf");
+        fs::write(&dst, "v1").unwrap();
+        // simulate the user having removed .~1~ by hand while .~2~ is still around
+        fs::write(dir.join("config-cache.wtf.~2~"), "manually numbered").unwrap();
+
+        let backup = backup_existing(&dst, BackupMode::Existing, "~").unwrap().unwrap();
+        assert_eq!(backup, dir.join("config-cache.wtf.~3~"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn numbered_continues_past_a_gap_left_by_a_deleted_backup() {
+        let dir = scratch_dir("backup");
+        let dst = dir.join("config-cache.wtf");
+        fs::write(&dst, "v1").unwrap();
+        // .~2~ was deleted by hand, leaving a gap between .~1~ and .~3~
+        fs::write(dir.join("config-cache.wtf.~1~"), "oldest").unwrap();
+        fs::write(dir.join("config-cache.wtf.~3~"), "most recent").unwrap();
+
+        let backup = backup_existing(&dst, BackupMode::Numbered, DEFAULT_SUFFIX).unwrap().unwrap();
+
+        assert_eq!(backup, dir.join("config-cache.wtf.~4~"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -10,7 +10,12 @@ use iced::{font, Font};
 
 use crate::operation::Operation;
 
+mod backup;
 mod operation;
+mod plan;
+#[cfg(test)]
+mod test_support;
+mod wine_prefix;
 mod wow;
 
 fn fonts() -> Vec<Cow<'static, [u8]>> {
@@ -23,6 +28,11 @@ fn fonts() -> Vec<Cow<'static, [u8]>> {
 }
 
 fn main() -> iced::Result {
+    // registers env_logger as the `log` facade's backend so the info/error records in
+    // operation.rs actually reach stderr (respects RUST_LOG; with it unset, env_logger
+    // disables all logging except the error level)
+    env_logger::init();
+
     let settings = iced::Settings {
         id: Some(String::from("wow-profile-copy-ng")),
         fonts: fonts(),
@@ -38,5 +48,5 @@ fn main() -> iced::Result {
     iced::application("wow-profile-copy-ng", Operation::update, Operation::view)
     .settings(settings)
     .theme(Operation::theme)
-    .run()
+    .run_with(Operation::new)
 }
@@ -4,10 +4,13 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+use crate::backup::{self, BackupMode};
+use crate::plan::{Plan, PlannedAction};
 use crate::wow::{self, Install, Version, Wtf};
-use iced::{alignment, border, font, Element, Fill, FillPortion, Font, Theme};
-use iced::widget::{button, checkbox, column, container, horizontal_rule, row, scrollable, text, Container};
-use std::{env, path::PathBuf, ffi::OsString, fs, io::Error};
+use chrono::Local;
+use iced::{alignment, border, font, Element, Fill, FillPortion, Font, Task, Theme};
+use iced::widget::{button, checkbox, column, container, horizontal_rule, row, scrollable, text, text_input, Container};
+use std::{path::{Path, PathBuf}, fs, io::{Error, Read}};
 use dark_light;
 
 
@@ -15,91 +18,100 @@ use dark_light;
 #[derive(Debug, Clone)]
 pub struct Operation {
     install: Option<Install>,
+    discovered: Vec<Install>,
     src_ver: Option<Version>,
     src_wtf: Option<Wtf>,
     dst_ver: Option<Version>,
     dst_wtf: Option<Wtf>,
     copy_logs: Option<Vec<String>>,
+    // the transcript of an actually-executed (or previously executed, via ViewHistory) copy,
+    // kept separate from copy_logs so a merely previewed plan can never be exported as if it
+    // had really run
+    last_transcript: Option<Vec<String>>,
     overwrite_account: bool,
+    backup_mode: BackupMode,
+    backup_suffix: String,
+    skip_identical: bool,
+    plan: Option<Plan>,
+    history: Vec<(String, Vec<String>)>,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     Install,
+    SelectInstall(Install),
     Version(Version, bool),
     Wtf(Wtf, bool),
     Copy,
+    Preview,
     Reset(bool),
     OverwriteToggle(bool),
+    BackupMode(BackupMode),
+    BackupSuffix(String),
+    SkipIdenticalToggle(bool),
+    ViewHistory(usize),
+    ExportLog,
+    InstallsDiscovered(Vec<Install>),
 }
 
+impl Operation {
+    // builds the initial (empty) state and kicks off install discovery in the background, so
+    // scanning the mount table and every candidate WTF tree never blocks the GUI on startup
+    pub fn new() -> (Self, Task<Message>) {
+        let op = Operation {
+            install: None,
+            discovered: vec![],
+            src_ver: None,
+            dst_ver: None,
+            src_wtf: None,
+            dst_wtf: None,
+            copy_logs: None,
+            last_transcript: None,
+            overwrite_account: true,
+            backup_mode: BackupMode::None,
+            backup_suffix: String::from(backup::DEFAULT_SUFFIX),
+            skip_identical: true,
+            plan: None,
+            history: vec![]
+        };
 
-impl std::default::Default for Operation {
-    fn default() -> Self {
-        let folder: OsString;
-        if cfg!(target_os = "windows") {
-            folder = OsString::from("C:\\Program Files (x86)\\World of Warcraft");
-        } else if cfg!(target_os = "macos") {
-            folder = OsString::from("/Applications/World of Warcraft");
-        } else if cfg!(target_os = "linux") {
-            let home = env::var_os("HOME");
-            if home.is_none() {
-                return Operation {
-                    install: None,
-                    src_ver: None,
-                    dst_ver: None,
-                    src_wtf: None,
-                    dst_wtf: None,
-                    copy_logs: None,
-                    overwrite_account: true
-                }
-            }
-            folder = PathBuf::from(home.unwrap())
-                .join("Games/battlenet/drive_c/Program Files (x86)/World of Warcraft")
-                .into_os_string();
-        } else {
-            return Operation {
-                install: None,
-                src_ver: None,
-                dst_ver: None,
-                src_wtf: None,
-                dst_wtf: None,
-                copy_logs: None,
-                overwrite_account: true
-            }
-        }
-
-        match wow::get_wow_install(folder) {
-            Ok(install) => {
-                Operation {
-                    install: Some(install),
-                    src_ver: None,
-                    dst_ver: None,
-                    src_wtf: None,
-                    dst_wtf: None,
-                    copy_logs: None,
-                    overwrite_account: true
-                }
-            }
-            Err(_) => {
-                Operation {
-                    install: None,
-                    src_ver: None,
-                    dst_ver: None,
-                    src_wtf: None,
-                    dst_wtf: None,
-                    copy_logs: None,
-                    overwrite_account: true
-                }
-            }
-        }
+        // iced's default native executor is its own thread-pool, not Tokio, so discovery is
+        // bridged back with a plain std::thread + oneshot channel instead of assuming a Tokio
+        // reactor is running (tokio::task::spawn_blocking would panic outside one)
+        let (tx, rx) = iced::futures::channel::oneshot::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(wow::discover_installs());
+        });
+        let discover = Task::perform(
+            async { rx.await.unwrap_or_default() },
+            Message::InstallsDiscovered
+        );
+
+        (op, discover)
     }
-}
 
-impl Operation {
     pub fn update(&mut self, message: Message) {
         match message {
-            Message::OverwriteToggle(o) => self.overwrite_account = o,
+            Message::InstallsDiscovered(installs) => self.discovered = installs,
+            Message::OverwriteToggle(o) => {
+                self.overwrite_account = o;
+                self.plan = None;
+            },
+            Message::BackupMode(mode) => {
+                self.backup_mode = mode;
+                self.plan = None;
+            },
+            Message::BackupSuffix(suffix) => {
+                let sanitized = backup::sanitize_suffix(&suffix);
+                // an empty suffix would make Simple back up a file onto itself, silently
+                // discarding the very file the backup is supposed to preserve
+                self.backup_suffix = if sanitized.is_empty() { String::from(backup::DEFAULT_SUFFIX) } else { sanitized };
+                self.plan = None;
+            },
+            Message::SkipIdenticalToggle(s) => {
+                self.skip_identical = s;
+                self.plan = None;
+            },
             Message::Install => {
                 let inst = wow::prompt_folder();
                 if inst.is_some() {
@@ -108,8 +120,17 @@ impl Operation {
                     self.dst_ver = None;
                     self.src_wtf = None;
                     self.dst_wtf = None;
+                    self.plan = None;
                 }
             },
+            Message::SelectInstall(install) => {
+                self.install = Some(install);
+                self.src_ver = None;
+                self.dst_ver = None;
+                self.src_wtf = None;
+                self.dst_wtf = None;
+                self.plan = None;
+            },
             Message::Reset(is_source) => {
                 if is_source {
                     self.src_ver = None;
@@ -118,6 +139,7 @@ impl Operation {
                     self.dst_ver = None;
                     self.dst_wtf = None;
                 }
+                self.plan = None;
             },
             Message::Version(ver, is_source) => {
                 if is_source {
@@ -125,6 +147,7 @@ impl Operation {
                 } else {
                     self.dst_ver = Some(ver)
                 }
+                self.plan = None;
             },
             Message::Wtf(wtf, is_source) => {
                 if is_source {
@@ -132,16 +155,44 @@ impl Operation {
                 } else {
                     self.dst_wtf = Some(wtf)
                 }
+                self.plan = None;
             },
-            Message::Copy => {
-                match do_copy(self) {
-                    Ok(l) => self.copy_logs = Some(l),
-                    // todo: show error dialog, rewind directory state
+            Message::Preview => {
+                match plan_copy(self) {
+                    Ok(plan) => {
+                        self.copy_logs = Some(plan.iter().map(|a| a.to_string()).collect());
+                        self.plan = Some(plan);
+                    },
                     Err(e) => {
                         self.copy_logs = Some(vec![e.to_string()]);
+                        self.plan = None;
                     }
                 }
             },
+            Message::Copy => {
+                if let Some(plan) = self.plan.clone() {
+                    let transcript = execute_plan(self, &plan);
+                    self.history.push((Local::now().format("%Y-%m-%d %H:%M:%S").to_string(), transcript.clone()));
+                    self.copy_logs = Some(transcript.clone());
+                    self.last_transcript = Some(transcript);
+                    self.plan = None;
+                }
+            },
+            Message::ViewHistory(i) => {
+                if let Some((_, transcript)) = self.history.get(i) {
+                    self.copy_logs = Some(transcript.clone());
+                    self.last_transcript = Some(transcript.clone());
+                    // an older transcript no longer matches whatever plan was previewed, so
+                    // "Go!" must not be able to execute it while that transcript is on screen
+                    self.plan = None;
+                }
+            },
+            Message::ExportLog => {
+                match export_log(self) {
+                    Ok(path) => log::info!("exported log to {:?}", path),
+                    Err(e) => log::error!("error exporting log: {}", e)
+                }
+            },
         }
     }
 
@@ -180,9 +231,20 @@ impl Operation {
 
     pub fn view(&self) -> Element<Message> {
         if self.install.is_none() {
+            let detected = column(
+                self.discovered.iter().map(|install| {
+                    button(text(install.install_dir.to_string_lossy().into_owned()).width(Fill).center())
+                    .on_press(Message::SelectInstall(install.clone()))
+                    .into()
+                })
+            )
+            .spacing(10);
+
             return container(
                 column![
-                    button(text("Select WoW Install Directory"))
+                    text(if self.discovered.is_empty() {"No installs detected"} else {"Detected installs"}),
+                    detected,
+                    button(text("Browse…"))
                     .on_press(Message::Install)
                 ]
                 .spacing(10)
@@ -225,10 +287,23 @@ impl Operation {
 
                 container(
                     column![
-                        text("Logs").font(Font {
-                            weight: font::Weight::Bold,
-                            ..Default::default()
-                        }),
+                        row![
+                            text("Logs").font(Font {
+                                weight: font::Weight::Bold,
+                                ..Default::default()
+                            }),
+                            row(
+                                self.history.iter().enumerate().map(|(i, (when, _))| {
+                                    button(text(when.clone()).size(12))
+                                    .on_press(Message::ViewHistory(i))
+                                    .into()
+                                })
+                            ).spacing(5),
+                            button("Export log")
+                            .on_press_maybe(self.last_transcript.is_some().then_some(Message::ExportLog))
+                        ]
+                        .spacing(10)
+                        .align_y(alignment::Vertical::Center),
                         horizontal_rule(2),
                         log
                     ]
@@ -247,11 +322,16 @@ impl Operation {
                 .width(Fill),
 
                 row![
+                    button("Preview")
+                    .padding(5)
+                    .on_press_maybe(self.is_ready().then_some(Message::Preview)),
+
                     button("Go!")
                     .padding(5)
-                    .on_press(Message::Copy)
+                    .on_press_maybe(self.plan.is_some().then_some(Message::Copy))
                     .style(button::success)
                 ]
+                .spacing(10)
             ]
             .spacing(10)
         )
@@ -296,18 +376,49 @@ impl Operation {
                 })
             )
         } else {
-            let toggle = if !is_source && 
+            let toggle = if !is_source &&
             (!self.is_same_account().unwrap_or(false) || !self.is_same_ver().unwrap_or(false)) {
                 Some(checkbox("Overwrite account-level variables?", self.overwrite_account)
                 .on_toggle(Message::OverwriteToggle))
             } else {
                 None
             };
+            let backup_selector = if !is_source {
+                Some(row(
+                    BackupMode::ALL.iter().map(|mode| {
+                        button(text(mode.to_string()))
+                        .style(if self.backup_mode == *mode {button::primary} else {button::secondary})
+                        .on_press(Message::BackupMode(*mode))
+                        .into()
+                    })
+                ).spacing(5))
+            } else {
+                None
+            };
+            let backup_suffix = if !is_source && self.backup_mode != BackupMode::None {
+                Some(row![
+                    text("Backup suffix:"),
+                    text_input(backup::DEFAULT_SUFFIX, &self.backup_suffix)
+                    .on_input(Message::BackupSuffix)
+                    .width(60)
+                ].spacing(5).align_y(alignment::Vertical::Center))
+            } else {
+                None
+            };
+            let skip_identical = if !is_source {
+                Some(checkbox("Skip identical files", self.skip_identical)
+                .on_toggle(Message::SkipIdenticalToggle))
+            } else {
+                None
+            };
             column![
                 text(format!("Version: {}", ver.as_ref().unwrap().to_string())),
                 text(format!("Character: {}", wtf.as_ref().unwrap().to_string())),
                 text(format!("Account: {}", wtf.as_ref().unwrap().account.to_str().unwrap_or_default()))
             ].push_maybe(toggle)
+            .push_maybe(backup_selector)
+            .push_maybe(backup_suffix)
+            .push_maybe(skip_identical)
         };
 
         container(
@@ -346,13 +457,56 @@ impl Operation {
     }
 }
 
-// does the actual copying of config files and savedvariables for a given install, source, and destination
-fn do_copy(op: &Operation) -> Result<Vec<String>, Error> {
+// compares two files byte-for-byte, short-circuiting on a differing length
+fn files_identical(a: &Path, b: &Path) -> Result<bool, Error> {
+    let (a_meta, b_meta) = match (fs::metadata(a), fs::metadata(b)) {
+        (Ok(a_meta), Ok(b_meta)) => (a_meta, b_meta),
+        _ => return Ok(false)
+    };
+
+    if a_meta.len() != b_meta.len() {
+        return Ok(false)
+    }
+
+    let mut a_file = fs::File::open(a)?;
+    let mut b_file = fs::File::open(b)?;
+    let mut a_buf = [0u8; 8192];
+    let mut b_buf = [0u8; 8192];
+
+    loop {
+        let a_read = a_file.read(&mut a_buf)?;
+        let b_read = b_file.read(&mut b_buf)?;
+        if a_read != b_read || a_buf[..a_read] != b_buf[..b_read] {
+            return Ok(false)
+        }
+        if a_read == 0 {
+            return Ok(true)
+        }
+    }
+}
+
+// decides what a copy of `src` onto `dst` would do, without touching the filesystem
+fn plan_file(op: &Operation, src: &Path, dst: &Path, plan: &mut Plan) {
+    if op.skip_identical && files_identical(src, dst).unwrap_or(false) {
+        plan.push(PlannedAction::SkipIdentical { dst: dst.to_path_buf() });
+        return
+    }
+
+    if op.backup_mode != BackupMode::None && dst.try_exists().unwrap_or(false) {
+        plan.push(PlannedAction::Backup { dst: dst.to_path_buf(), mode: op.backup_mode });
+    }
+
+    plan.push(PlannedAction::Copy { src: src.to_path_buf(), dst: dst.to_path_buf() });
+}
+
+// builds the list of actions a copy of `op`'s source onto its target would perform, without
+// writing anything; `execute_plan` executes exactly this plan, so the preview can never lie
+fn plan_copy(op: &Operation) -> Result<Plan, Error> {
     if !op.is_ready() {
         return Err(Error::other("operation not ready for copying!"))
     }
 
-    let mut log: Vec<String> = vec![];
+    let mut plan: Plan = vec![];
     let account_files: [&str; 4] = ["bindings-cache.wtf", "config-cache.wtf", "macros-cache.txt", "edit-mode-cache-account.txt"];
     let character_files: [&str; 5] = ["AddOns.txt", "config-cache.wtf", "layout-local.txt", "macros-cache.txt", "edit-mode-cache-character.txt"];
 
@@ -372,17 +526,13 @@ fn do_copy(op: &Operation) -> Result<Vec<String>, Error> {
         .join(&dst_account);
 
     if src_account == dst_account || !op.overwrite_account {
-        log.push(String::from("skipping account copy."));
+        plan.push(PlannedAction::SkipAccount);
     } else {
         // client configuration
         for file in account_files {
             let src = src_root.join(file);
             let dst = dst_root.join(file);
-            let output = match fs::copy(&src, &dst) {
-                Ok(_) => format!("copied {:?}", src.file_name().unwrap_or_default()),
-                Err(e) => format!("error copying {:?}: {}", src.as_os_str(), e.to_string())
-            };
-            log.push(output);
+            plan_file(op, &src, &dst, &mut plan);
         }
 
         // account saved variables
@@ -405,19 +555,10 @@ fn do_copy(op: &Operation) -> Result<Vec<String>, Error> {
             };
             let src = src_savedvars.join(e.file_name());
             let dst = dst_savedvars.join(e.file_name());
-            let output = match fs::copy(&src, &dst) {
-                Ok(_) => format!("copied {:?}", src.file_name().unwrap_or_default()),
-                Err(e) => format!("error copying {:?}: {}", src.as_os_str(), e.to_string())
-            };
-            log.push(output);
+            plan_file(op, &src, &dst, &mut plan);
         }
 
-        let cache = dst_root.join("cache.md5");
-        let output = match fs::remove_file(&cache) {
-            Ok(_) => format!("removed {:?}", cache.file_name().unwrap_or_default()),
-            Err(e) => format!("error removing {:?}: {}", cache.as_os_str(), e.to_string())
-        };
-        log.push(output);
+        plan.push(PlannedAction::RemoveCache { cache: dst_root.join("cache.md5") });
     }
 
     // character configuration
@@ -434,20 +575,15 @@ fn do_copy(op: &Operation) -> Result<Vec<String>, Error> {
     for file in character_files {
         let src = src_character.join(file);
         let dst = dst_character.join(file);
-        let output = match fs::copy(&src, &dst) {
-            Ok(_) => format!("copied {:?}", dst.file_name().unwrap_or_default()),
-            Err(e) => format!("error copying {:?}: {}", dst.as_os_str(), e.to_string())
-        };
-        log.push(output);
+        plan_file(op, &src, &dst, &mut plan);
     }
 
     // character saved variables
     let src_savedvars = src_character.join("SavedVariables");
     let dst_savedvars = dst_character.join("SavedVariables");
-    
+
     if !dst_savedvars.try_exists()? {
-        log.push(format!("destination savedvariables dir missing, creating: {:?}", dst_savedvars.as_os_str()));
-        fs::create_dir_all(&dst_savedvars)?;
+        plan.push(PlannedAction::CreateDir { dir: dst_savedvars.clone() });
     }
 
     let entries = fs::read_dir(&src_savedvars)?
@@ -465,19 +601,277 @@ fn do_copy(op: &Operation) -> Result<Vec<String>, Error> {
         };
         let src = src_savedvars.join(e.file_name());
         let dst = dst_savedvars.join(e.file_name());
-        let output = match fs::copy(&src, &dst) {
-            Ok(_) => format!("copied {:?}", dst.file_name().unwrap_or_default()),
-            Err(e) => format!("error copying {:?}: {}", dst.as_os_str(), e.to_string())
+        plan_file(op, &src, &dst, &mut plan);
+    }
+
+    plan.push(PlannedAction::RemoveCache { cache: dst_character.join("cache.md5") });
+
+    Ok(plan)
+}
+
+// carries out a previously computed plan, performing the filesystem writes it describes and
+// logging exactly what happened for each action
+fn execute_plan(op: &Operation, plan: &Plan) -> Vec<String> {
+    let mut transcript: Vec<String> = vec![];
+    // `plan_file` always pairs a `Backup` with the `Copy` it's meant to protect; if the backup
+    // failed, that `Copy` must not run either, or the overwrite proceeds with no safety net
+    let mut failed_backup_dst: Option<PathBuf> = None;
+
+    for action in plan {
+        if let PlannedAction::Copy { dst, .. } = action {
+            if failed_backup_dst.as_deref() == Some(dst.as_path()) {
+                let output = format!("skipped copying {:?}: its backup failed", dst.file_name().unwrap_or_default());
+                log::error!("{}", output);
+                transcript.push(output);
+                failed_backup_dst = None;
+                continue;
+            }
+        }
+        failed_backup_dst = None;
+
+        let (ok, output) = match action {
+            PlannedAction::SkipAccount => (true, String::from("skipping account copy.")),
+            PlannedAction::Backup { dst, mode } => {
+                match backup::backup_existing(dst, *mode, &op.backup_suffix) {
+                    Ok(Some(backup)) => (true, format!("backed up {:?} to {:?}", dst.file_name().unwrap_or_default(), backup.file_name().unwrap_or_default())),
+                    Ok(None) => continue,
+                    Err(e) => {
+                        failed_backup_dst = Some(dst.clone());
+                        (false, format!("error backing up {:?}: {}", dst.as_os_str(), e.to_string()))
+                    }
+                }
+            },
+            PlannedAction::Copy { src, dst } => {
+                match fs::copy(src, dst) {
+                    Ok(_) => (true, format!("copied {:?}", dst.file_name().unwrap_or_default())),
+                    Err(e) => (false, format!("error copying {:?}: {}", dst.as_os_str(), e.to_string()))
+                }
+            },
+            PlannedAction::SkipIdentical { dst } => (true, format!("unchanged: {:?}", dst.file_name().unwrap_or_default())),
+            PlannedAction::CreateDir { dir } => {
+                match fs::create_dir_all(dir) {
+                    Ok(_) => (true, format!("created directory {:?}", dir.as_os_str())),
+                    Err(e) => (false, format!("error creating directory {:?}: {}", dir.as_os_str(), e.to_string()))
+                }
+            },
+            PlannedAction::RemoveCache { cache } => {
+                match fs::remove_file(cache) {
+                    Ok(_) => (true, format!("removed {:?}", cache.file_name().unwrap_or_default())),
+                    Err(e) => (false, format!("error removing {:?}: {}", cache.as_os_str(), e.to_string()))
+                }
+            },
         };
-        log.push(output);
+
+        if ok {
+            log::info!("{}", output);
+        } else {
+            log::error!("{}", output);
+        }
+        transcript.push(output);
     }
 
-    let cache = dst_character.join("cache.md5");
-    let output = match fs::remove_file(&cache) {
-        Ok(_) => format!("removed {:?}", cache.file_name().unwrap_or_default()),
-        Err(e) => format!("error removing {:?}: {}", cache.as_os_str(), e.to_string())
-    };
-    log.push(output);
+    transcript
+}
+
+// writes the last actually-executed transcript to a timestamped file inside the install
+// directory; deliberately reads last_transcript rather than copy_logs, since copy_logs may
+// currently be showing an unexecuted preview
+fn export_log(op: &Operation) -> Result<PathBuf, Error> {
+    let logs = op.last_transcript.as_ref().ok_or_else(|| Error::other("no executed copy to export yet"))?;
+    let install_dir = &op.install.as_ref().ok_or_else(|| Error::other("no install selected"))?.install_dir;
+
+    let filename = format!("wow-profile-copy-{}.txt", Local::now().format("%Y%m%d-%H%M%S"));
+    let path = install_dir.join(filename);
+    fs::write(&path, logs.join("\n"))?;
+    Ok(path)
+}
 
-    Ok(log)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scratch_dir;
+    use std::ffi::OsString;
+
+    // lays out a fake install with one source and one destination account/character, so
+    // `plan_copy` can be exercised without a real WoW install on disk
+    fn fixture(install_dir: &Path) -> Operation {
+        let ver = Version { name: String::from("_retail_"), wtfs: vec![] };
+        let src_wtf = Wtf {
+            account: OsString::from("SRCACCT"),
+            realm: OsString::from("Realm"),
+            character: OsString::from("Char"),
+            has_vars: true,
+        };
+        let dst_wtf = Wtf {
+            account: OsString::from("DSTACCT"),
+            realm: OsString::from("Realm"),
+            character: OsString::from("Char"),
+            has_vars: true,
+        };
+
+        let src_root = install_dir.join("_retail_/WTF/Account/SRCACCT");
+        let dst_root = install_dir.join("_retail_/WTF/Account/DSTACCT");
+        let src_character = src_root.join("Realm/Char");
+        let dst_character = dst_root.join("Realm/Char");
+
+        fs::create_dir_all(src_root.join("SavedVariables")).unwrap();
+        fs::create_dir_all(dst_root.join("SavedVariables")).unwrap();
+        fs::create_dir_all(&src_character).unwrap();
+        fs::create_dir_all(&dst_character).unwrap();
+
+        fs::write(src_root.join("bindings-cache.wtf"), "src bindings").unwrap();
+        fs::write(src_root.join("SavedVariables/Foo.lua"), "foo contents").unwrap();
+        fs::write(dst_root.join("SavedVariables/Foo.lua"), "foo contents").unwrap();
+
+        fs::create_dir_all(src_character.join("SavedVariables")).unwrap();
+        fs::write(src_character.join("AddOns.txt"), "src addons").unwrap();
+        fs::write(src_character.join("SavedVariables/Bar.lua"), "bar contents").unwrap();
+
+        Operation {
+            install: Some(Install { install_dir: install_dir.to_path_buf(), versions: vec![ver.clone()] }),
+            discovered: vec![],
+            src_ver: Some(ver.clone()),
+            dst_ver: Some(ver),
+            src_wtf: Some(src_wtf),
+            dst_wtf: Some(dst_wtf),
+            copy_logs: None,
+            last_transcript: None,
+            overwrite_account: true,
+            backup_mode: BackupMode::None,
+            backup_suffix: String::from(backup::DEFAULT_SUFFIX),
+            skip_identical: true,
+            plan: None,
+            history: vec![],
+        }
+    }
+
+    #[test]
+    fn files_identical_compares_contents_not_just_length() {
+        let dir = scratch_dir("operation");
+        let a = dir.join("a.lua");
+        let b = dir.join("b.lua");
+        let c = dir.join("c.lua");
+        fs::write(&a, "same").unwrap();
+        fs::write(&b, "same").unwrap();
+        fs::write(&c, "diff").unwrap();
+
+        assert!(files_identical(&a, &b).unwrap());
+        assert!(!files_identical(&a, &c).unwrap());
+        assert!(!files_identical(&a, &dir.join("missing.lua")).unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn plan_file_skips_identical_destinations_when_enabled() {
+        let dir = scratch_dir("operation");
+        let src = dir.join("src.lua");
+        let dst = dir.join("dst.lua");
+        fs::write(&src, "same").unwrap();
+        fs::write(&dst, "same").unwrap();
+
+        let op = fixture(&dir);
+        let mut plan = vec![];
+        plan_file(&op, &src, &dst, &mut plan);
+
+        assert!(matches!(plan.as_slice(), [PlannedAction::SkipIdentical { .. }]));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn plan_file_backs_up_then_copies_over_a_differing_destination() {
+        let dir = scratch_dir("operation");
+        let src = dir.join("src.lua");
+        let dst = dir.join("dst.lua");
+        fs::write(&src, "new").unwrap();
+        fs::write(&dst, "old").unwrap();
+
+        let mut op = fixture(&dir);
+        op.backup_mode = BackupMode::Simple;
+        let mut plan = vec![];
+        plan_file(&op, &src, &dst, &mut plan);
+
+        assert!(matches!(plan.as_slice(), [PlannedAction::Backup { .. }, PlannedAction::Copy { .. }]));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn execute_plan_skips_the_paired_copy_when_its_backup_fails() {
+        let dir = scratch_dir("operation");
+        let src = dir.join("src.lua");
+        let dst = dir.join("dst.lua");
+        fs::write(&src, "new").unwrap();
+        fs::write(&dst, "old").unwrap();
+        // occupy the backup path with a directory so `fs::rename` fails
+        fs::create_dir_all(dir.join("dst.lua~")).unwrap();
+
+        let op = fixture(&dir);
+        let plan = vec![
+            PlannedAction::Backup { dst: dst.clone(), mode: BackupMode::Simple },
+            PlannedAction::Copy { src: src.clone(), dst: dst.clone() },
+        ];
+
+        execute_plan(&op, &plan);
+
+        // the copy must not have clobbered the destination the failed backup was meant to preserve
+        assert_eq!(fs::read_to_string(&dst).unwrap(), "old");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn plan_copy_covers_account_and_character_files() {
+        let dir = scratch_dir("operation");
+        let op = fixture(&dir);
+
+        let plan = plan_copy(&op).unwrap();
+
+        // bindings-cache.wtf has no destination yet, so it's a plain copy
+        assert!(plan.iter().any(|a| matches!(a, PlannedAction::Copy { dst, .. } if dst.ends_with("bindings-cache.wtf"))));
+        // Foo.lua is byte-identical on both sides, and skip_identical is on
+        assert!(plan.iter().any(|a| matches!(a, PlannedAction::SkipIdentical { dst } if dst.ends_with("Foo.lua"))));
+        // the destination character has no SavedVariables folder yet
+        assert!(plan.iter().any(|a| matches!(a, PlannedAction::CreateDir { dir } if dir.ends_with("SavedVariables"))));
+        // AddOns.txt is new to the destination character
+        assert!(plan.iter().any(|a| matches!(a, PlannedAction::Copy { dst, .. } if dst.ends_with("AddOns.txt"))));
+        // both the account and character cache.md5 get invalidated
+        assert_eq!(plan.iter().filter(|a| matches!(a, PlannedAction::RemoveCache { .. })).count(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn plan_copy_skips_account_when_accounts_match() {
+        let dir = scratch_dir("operation");
+        let mut op = fixture(&dir);
+        op.dst_wtf = op.src_wtf.clone();
+
+        let plan = plan_copy(&op).unwrap();
+
+        assert!(matches!(plan.first(), Some(PlannedAction::SkipAccount)));
+        assert!(!plan.iter().any(|a| matches!(a, PlannedAction::Copy { dst, .. } if dst.ends_with("bindings-cache.wtf"))));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn export_log_writes_the_last_transcript_next_to_the_install() {
+        let dir = scratch_dir("operation");
+        let mut op = fixture(&dir);
+        op.last_transcript = Some(vec![String::from("copied: foo"), String::from("unchanged: bar")]);
+
+        let path = export_log(&op).unwrap();
+
+        assert!(path.starts_with(&dir));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "copied: foo\nunchanged: bar");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn export_log_errors_without_an_executed_copy() {
+        let dir = scratch_dir("operation");
+        let op = fixture(&dir);
+
+        let err = export_log(&op).unwrap_err();
+
+        assert_eq!(err.to_string(), "no executed copy to export yet");
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,38 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+// a side-effect-free description of what a copy run would do, so the UI can preview it before
+// `execute_plan` actually touches the filesystem
+
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::backup::BackupMode;
+
+#[derive(Debug, Clone)]
+pub enum PlannedAction {
+    SkipAccount,
+    Backup { dst: PathBuf, mode: BackupMode },
+    Copy { src: PathBuf, dst: PathBuf },
+    SkipIdentical { dst: PathBuf },
+    CreateDir { dir: PathBuf },
+    RemoveCache { cache: PathBuf },
+}
+
+impl fmt::Display for PlannedAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlannedAction::SkipAccount => write!(f, "skip account copy (same account, or disabled)"),
+            PlannedAction::Backup { dst, mode } => write!(f, "back up {:?} ({})", dst.file_name().unwrap_or_default(), mode),
+            PlannedAction::Copy { src, dst } => write!(f, "copy {:?} -> {:?}", src, dst),
+            PlannedAction::SkipIdentical { dst } => write!(f, "unchanged: {:?}", dst.file_name().unwrap_or_default()),
+            PlannedAction::CreateDir { dir } => write!(f, "create directory {:?}", dir),
+            PlannedAction::RemoveCache { cache } => write!(f, "remove {:?}", cache.file_name().unwrap_or_default()),
+        }
+    }
+}
+
+pub type Plan = Vec<PlannedAction>;
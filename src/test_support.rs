@@ -0,0 +1,23 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+// shared fixtures for unit tests across modules, so each module's test suite doesn't
+// reinvent the same scratch-directory bookkeeping
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+// gives each test its own scratch directory, named after the calling module, so they can
+// run in parallel without stepping on each other's files
+pub fn scratch_dir(module: &str) -> PathBuf {
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let dir = std::env::temp_dir().join(format!("wow-profile-copy-{}-test-{}-{}", module, std::process::id(), n));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,99 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+// locates WoW installs inside the Wine/Proton prefixes used by Steam Play, Lutris and Bottles,
+// so Linux players on those launchers don't have to browse for their install by hand
+
+use std::fs;
+use std::path::PathBuf;
+
+use crate::wow::{get_wow_install, Install};
+
+#[cfg(target_os = "linux")]
+const PROGRAM_FILES_PATH: &str = "Program Files (x86)/World of Warcraft";
+
+#[cfg(target_os = "linux")]
+pub fn discover_prefix_installs() -> Vec<Install> {
+    prefix_roots().into_iter()
+        .filter_map(|prefix| get_wow_install(prefix.join("drive_c").join(PROGRAM_FILES_PATH).into_os_string()).ok())
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn discover_prefix_installs() -> Vec<Install> {
+    vec![]
+}
+
+// every prefix root worth checking for a drive_c: Steam Play, Lutris and Bottles each lay
+// theirs out differently, so each gets its own probe
+#[cfg(target_os = "linux")]
+fn prefix_roots() -> Vec<PathBuf> {
+    let mut roots = vec![];
+
+    if let Some(data_dir) = dirs::data_dir() {
+        // Steam Play (Proton): ~/.local/share/Steam/steamapps/compatdata/<appid>/pfx
+        roots.extend(subdirs(data_dir.join("Steam/steamapps/compatdata")).map(|d| d.join("pfx")));
+
+        // Bottles (native install): ~/.local/share/bottles/bottles/<name>
+        roots.extend(subdirs(data_dir.join("bottles/bottles")));
+
+        // Lutris's prefix manager keeps shared/standalone prefixes here; per-game prefixes
+        // that live alongside the install are covered by the ~/Games scan below
+        roots.extend(subdirs(data_dir.join("lutris/wineprefixes")));
+    }
+
+    if let Some(home) = dirs::home_dir() {
+        roots.extend(subdirs(home.join("Games")));
+
+        // Bottles via Flatpak's sandboxed data dir
+        if let Ok(apps) = fs::read_dir(home.join(".var/app")) {
+            for app in apps.flatten().filter(|a| a.file_name().to_string_lossy().contains("usebottles")) {
+                roots.extend(subdirs(app.path().join("data/bottles/bottles")));
+            }
+        }
+    }
+
+    roots
+}
+
+#[cfg(target_os = "linux")]
+fn subdirs(dir: PathBuf) -> impl Iterator<Item = PathBuf> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::test_support::scratch_dir;
+
+    #[test]
+    fn subdirs_returns_only_directories() {
+        let dir = scratch_dir("wine_prefix");
+        fs::create_dir_all(dir.join("pfx-a")).unwrap();
+        fs::create_dir_all(dir.join("pfx-b")).unwrap();
+        fs::write(dir.join("not-a-prefix.txt"), "").unwrap();
+
+        let mut found: Vec<PathBuf> = subdirs(dir.clone()).collect();
+        found.sort();
+
+        assert_eq!(found, vec![dir.join("pfx-a"), dir.join("pfx-b")]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn subdirs_of_a_missing_root_is_empty() {
+        let dir = scratch_dir("wine_prefix");
+        let missing = dir.join("does-not-exist");
+
+        assert_eq!(subdirs(missing).count(), 0);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
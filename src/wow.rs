@@ -0,0 +1,277 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
+use std::io::Error;
+use std::path::{Path, PathBuf};
+
+#[cfg(target_os = "linux")]
+use lfs_core::{read_mounts, ReadOptions};
+
+/// A single character's SavedVariables location within an account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Wtf {
+    pub account: OsString,
+    pub realm: OsString,
+    pub character: OsString,
+    pub has_vars: bool,
+}
+
+impl fmt::Display for Wtf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} - {}", self.realm.to_string_lossy(), self.character.to_string_lossy())
+    }
+}
+
+/// A game version folder (`_retail_`, `_classic_`, ...) and the characters found in its WTF tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Version {
+    pub name: String,
+    pub wtfs: Vec<Wtf>,
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Install {
+    pub install_dir: PathBuf,
+    pub versions: Vec<Version>,
+}
+
+const VERSION_FOLDERS: [&str; 4] = ["_retail_", "_classic_", "_classic_era_", "_ptr_"];
+
+/// Inspects `install_dir` for version folders with a `WTF` tree and builds an `Install` from them.
+pub fn get_wow_install(install_dir: OsString) -> Result<Install, Error> {
+    let install_dir = PathBuf::from(install_dir);
+    let mut versions = vec![];
+
+    for folder in VERSION_FOLDERS {
+        let version_dir = install_dir.join(folder);
+        if !version_dir.join("WTF").join("Account").try_exists()? {
+            continue
+        }
+        versions.push(Version {
+            name: folder.to_string(),
+            wtfs: read_wtfs(&version_dir),
+        });
+    }
+
+    if versions.is_empty() {
+        return Err(Error::other("no WoW version folders with a WTF tree found"))
+    }
+
+    Ok(Install { install_dir, versions })
+}
+
+// walks {version_dir}/WTF/Account/{account}/{realm}/{character} and collects every character found
+fn read_wtfs(version_dir: &Path) -> Vec<Wtf> {
+    let mut wtfs = vec![];
+    let account_root = version_dir.join("WTF").join("Account");
+
+    let Ok(accounts) = fs::read_dir(&account_root) else { return wtfs };
+    for account in accounts.flatten() {
+        if !account.path().is_dir() {
+            continue
+        }
+        let account_name = account.file_name();
+
+        let Ok(realms) = fs::read_dir(account.path()) else { continue };
+        for realm in realms.flatten() {
+            if !realm.path().is_dir() || realm.file_name() == "SavedVariables" {
+                continue
+            }
+            let realm_name = realm.file_name();
+
+            let Ok(characters) = fs::read_dir(realm.path()) else { continue };
+            for character in characters.flatten() {
+                if !character.path().is_dir() {
+                    continue
+                }
+                let has_vars = character.path().join("SavedVariables").try_exists().unwrap_or(false);
+                wtfs.push(Wtf {
+                    account: account_name.clone(),
+                    realm: realm_name.clone(),
+                    character: character.file_name(),
+                    has_vars,
+                });
+            }
+        }
+    }
+
+    wtfs
+}
+
+/// Opens a native folder picker and resolves the chosen folder to an `Install`.
+pub fn prompt_folder() -> Option<Install> {
+    let folder = rfd::FileDialog::new().pick_folder()?;
+    get_wow_install(folder.into_os_string()).ok()
+}
+
+// subdirectories, relative to a mount point, worth checking for a WoW install
+#[cfg(target_os = "linux")]
+const LIKELY_ROOTS: [&str; 3] = [
+    "Program Files (x86)/World of Warcraft",
+    "Games/World of Warcraft",
+    "World of Warcraft",
+];
+
+// pseudo/virtual filesystems that never hold a real WoW install and aren't worth scanning
+#[cfg(target_os = "linux")]
+const PSEUDO_FILESYSTEMS: [&str; 14] = [
+    "proc", "sysfs", "devtmpfs", "devpts", "tmpfs", "overlay", "squashfs", "cgroup", "cgroup2",
+    "debugfs", "tracefs", "securityfs", "pstore", "autofs",
+];
+
+// network filesystems: a stale or unresponsive server can make even a stat() on these hang
+// indefinitely, which would wedge discovery, so they're skipped rather than probed
+#[cfg(target_os = "linux")]
+const NETWORK_FILESYSTEMS: [&str; 8] = [
+    "nfs", "nfs4", "cifs", "smbfs", "smb3", "fuse.sshfs", "afs", "9p",
+];
+
+/// Enumerates mounted filesystems (as `broot`'s `lfs-core` does) and scans each real mount point's
+/// likely roots for a WoW install, so multi-drive setups don't require manual browsing. Also
+/// includes installs found inside Wine/Proton prefixes.
+///
+/// This walks every candidate WTF tree synchronously, so callers on a GUI thread should run it
+/// in the background (e.g. behind an `iced::Task`) rather than calling it during startup.
+pub fn discover_installs() -> Vec<Install> {
+    let mut installs = platform_installs();
+    installs.extend(crate::wine_prefix::discover_prefix_installs());
+    installs
+}
+
+// `lfs-core` reads Linux's `/proc` mount table and has no real Windows/macOS backend, so mount
+// enumeration is Linux-only; Windows and macOS fall back to the single well-known install path,
+// same as this tool always has.
+#[cfg(target_os = "linux")]
+fn platform_installs() -> Vec<Install> {
+    let mut installs = vec![];
+
+    let Ok(mounts) = read_mounts(&ReadOptions::default()) else { return installs };
+    for mount in mounts {
+        if PSEUDO_FILESYSTEMS.contains(&mount.info.fs_type.as_str())
+        || NETWORK_FILESYSTEMS.contains(&mount.info.fs_type.as_str()) {
+            continue
+        }
+
+        for root in LIKELY_ROOTS {
+            let candidate = mount.info.mount_point.join(root);
+            if let Ok(install) = get_wow_install(candidate.into_os_string()) {
+                installs.push(install);
+            }
+        }
+    }
+
+    installs
+}
+
+#[cfg(target_os = "windows")]
+fn platform_installs() -> Vec<Install> {
+    get_wow_install(OsString::from("C:\\Program Files (x86)\\World of Warcraft"))
+        .into_iter()
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn platform_installs() -> Vec<Install> {
+    get_wow_install(OsString::from("/Applications/World of Warcraft"))
+        .into_iter()
+        .collect()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+fn platform_installs() -> Vec<Install> {
+    vec![]
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scratch_dir;
+
+    fn character_dir(version_dir: &Path, account: &str, realm: &str, character: &str) -> PathBuf {
+        let dir = version_dir.join("WTF").join("Account").join(account).join(realm).join(character);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn version_folder_without_a_wtf_tree_is_skipped() {
+        let dir = scratch_dir("wow");
+        fs::create_dir_all(dir.join("_retail_")).unwrap();
+
+        let err = get_wow_install(dir.clone().into_os_string()).unwrap_err();
+        assert_eq!(err.to_string(), "no WoW version folders with a WTF tree found");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reads_multiple_accounts_realms_and_characters() {
+        let dir = scratch_dir("wow");
+        let version_dir = dir.join("_retail_");
+        character_dir(&version_dir, "ACCOUNT1", "Realm-A", "Alice");
+        character_dir(&version_dir, "ACCOUNT1", "Realm-A", "Bob");
+        character_dir(&version_dir, "ACCOUNT1", "Realm-B", "Carol");
+        character_dir(&version_dir, "ACCOUNT2", "Realm-A", "Dave");
+
+        let install = get_wow_install(dir.clone().into_os_string()).unwrap();
+
+        assert_eq!(install.versions.len(), 1);
+        assert_eq!(install.versions[0].name, "_retail_");
+        assert_eq!(install.versions[0].wtfs.len(), 4);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn has_vars_reflects_whether_saved_variables_exists() {
+        let dir = scratch_dir("wow");
+        let version_dir = dir.join("_retail_");
+        let with_vars = character_dir(&version_dir, "ACCOUNT1", "Realm-A", "Alice");
+        fs::create_dir_all(with_vars.join("SavedVariables")).unwrap();
+        character_dir(&version_dir, "ACCOUNT1", "Realm-A", "Bob");
+
+        let wtfs = read_wtfs(&version_dir);
+
+        let alice = wtfs.iter().find(|w| w.character == "Alice").unwrap();
+        let bob = wtfs.iter().find(|w| w.character == "Bob").unwrap();
+        assert!(alice.has_vars);
+        assert!(!bob.has_vars);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn account_level_saved_variables_dir_is_not_mistaken_for_a_realm() {
+        let dir = scratch_dir("wow");
+        let version_dir = dir.join("_retail_");
+        let account_dir = version_dir.join("WTF").join("Account").join("ACCOUNT1");
+        fs::create_dir_all(account_dir.join("SavedVariables")).unwrap();
+        character_dir(&version_dir, "ACCOUNT1", "Realm-A", "Alice");
+
+        let wtfs = read_wtfs(&version_dir);
+
+        assert_eq!(wtfs.len(), 1);
+        assert_eq!(wtfs[0].character, "Alice");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn empty_account_root_yields_no_wtfs() {
+        let dir = scratch_dir("wow");
+        let version_dir = dir.join("_retail_");
+        fs::create_dir_all(version_dir.join("WTF").join("Account")).unwrap();
+
+        let wtfs = read_wtfs(&version_dir);
+
+        assert!(wtfs.is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}